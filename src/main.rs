@@ -6,41 +6,50 @@ use structopt::StructOpt;
 
 pub type Result<T> = anyhow::Result<T>;
 
-fn github(repo_url: &str, branch_or_commit: &str, path: &str, line: Option<u16>) -> Result<String> {
-    let mut url = String::new();
-
-    fmt::write(
-        &mut url,
-        format_args!(
-            "{url}/blob/{branch}/{path}",
-            url = repo_url,
-            branch = branch_or_commit,
-            path = path
-        ),
-    )?;
-
-    if let Some(line) = line {
-        fmt::write(&mut url, format_args!("#L{}", line))?;
+// Per-host path scheme for the `{url}/<scheme>/{path}` part of the file url.
+// `is_commit` lets hosts that distinguish branch vs. commit refs in the path
+// (e.g. Gitea/Forgejo's `src/branch/` vs `src/commit/`) pick the right segment.
+fn path_segment(platform: &Platform, branch_or_commit: &str, is_commit: bool, path: &str) -> String {
+    match platform {
+        Platform::Github => format!("blob/{}/{}", branch_or_commit, path),
+        Platform::Gitlab => format!("-/blob/{}/{}", branch_or_commit, path),
+        Platform::Bitbucket => format!("src/{}/{}", branch_or_commit, path),
+        Platform::Gitea => {
+            let kind = if is_commit { "commit" } else { "branch" };
+            format!("src/{}/{}/{}", kind, branch_or_commit, path)
+        }
     }
+}
 
-    Ok(url)
+// Per-host line anchor syntax (e.g. Bitbucket uses `#lines-{n}` instead of `#L{n}`).
+fn line_fragment(platform: &Platform, line: u16) -> String {
+    match platform {
+        Platform::Bitbucket => format!("#lines-{}", line),
+        _ => format!("#L{}", line),
+    }
 }
 
-fn gitlab(repo_url: &str, branch_or_commit: &str, path: &str, line: Option<u16>) -> Result<String> {
+fn file_url(
+    platform: &Platform,
+    repo_url: &str,
+    branch_or_commit: &str,
+    is_commit: bool,
+    path: &str,
+    line: Option<u16>,
+) -> Result<String> {
     let mut url = String::new();
 
     fmt::write(
         &mut url,
         format_args!(
-            "{url}/-/blob/{branch}/{path}",
+            "{url}/{segment}",
             url = repo_url,
-            branch = branch_or_commit,
-            path = path
+            segment = path_segment(platform, branch_or_commit, is_commit, path)
         ),
     )?;
 
     if let Some(line) = line {
-        fmt::write(&mut url, format_args!("#L{}", line))?;
+        fmt::write(&mut url, format_args!("{}", line_fragment(platform, line)))?;
     }
 
     Ok(url)
@@ -93,6 +102,7 @@ fn main() -> Result<()> {
     let head = repo.head()?;
 
     let branch_or_commit: String;
+    let is_commit = !head.is_branch();
 
     if head.is_branch() {
         branch_or_commit = if let Some(name) = repo.head()?.name() {
@@ -117,18 +127,20 @@ fn main() -> Result<()> {
     }
 
     if let Some(platform) = opt.platform {
-        let file_url = match platform {
-            Platform::Github => github(&url, &branch_or_commit, file, opt.line)?,
-            Platform::Gitlab => gitlab(&url, &branch_or_commit, file, opt.line)?,
-        };
-
-        println!("{}", file_url);
+        let url = file_url(&platform, &url, &branch_or_commit, is_commit, file, opt.line)?;
+        println!("{}", url);
     } else if url.contains("github") {
-        let file_url = github(&url, &branch_or_commit, file, opt.line).unwrap();
-        println!("{}", file_url);
+        let url = file_url(&Platform::Github, &url, &branch_or_commit, is_commit, file, opt.line)?;
+        println!("{}", url);
     } else if url.contains("gitlab") {
-        let file_url = gitlab(&url, &branch_or_commit, file, opt.line).unwrap();
-        println!("{}", file_url);
+        let url = file_url(&Platform::Gitlab, &url, &branch_or_commit, is_commit, file, opt.line)?;
+        println!("{}", url);
+    } else if url.contains("bitbucket") {
+        let url = file_url(&Platform::Bitbucket, &url, &branch_or_commit, is_commit, file, opt.line)?;
+        println!("{}", url);
+    } else if url.contains("gitea") || url.contains("forgejo") {
+        let url = file_url(&Platform::Gitea, &url, &branch_or_commit, is_commit, file, opt.line)?;
+        println!("{}", url);
     } else {
         return Err(anyhow!("unknown url, try passing --url param"));
     }
@@ -149,7 +161,7 @@ struct Opt {
         short,
         long,
         parse(try_from_str = parse_platform),
-        help="Platform : gitlab or github" 
+        help="Platform : github, gitlab, bitbucket or gitea/forgejo"
     )]
     platform: Option<Platform>,
 
@@ -161,12 +173,16 @@ struct Opt {
 enum Platform {
     Github,
     Gitlab,
+    Bitbucket,
+    Gitea,
 }
 
 fn parse_platform(p: &str) -> Result<Platform> {
     match p.to_lowercase().as_str() {
         "github" => Ok(Platform::Github),
         "gitlab" => Ok(Platform::Gitlab),
+        "bitbucket" => Ok(Platform::Bitbucket),
+        "gitea" | "forgejo" => Ok(Platform::Gitea),
         _ => Err(anyhow!("Invalid platform {}", p)),
     }
 }
@@ -176,9 +192,11 @@ mod test {
 
     #[test]
     fn github() {
-        let url = crate::github(
+        let url = crate::file_url(
+            &crate::Platform::Github,
             "https://github.com/nbouliol/git-files",
             "master",
+            false,
             "readme.md",
             None,
         );
@@ -189,9 +207,11 @@ mod test {
 
     #[test]
     fn github_with_line() {
-        let url = crate::github(
+        let url = crate::file_url(
+            &crate::Platform::Github,
             "https://github.com/nbouliol/git-files",
             "master",
+            false,
             "readme.md",
             Some(5),
         );
@@ -202,9 +222,11 @@ mod test {
 
     #[test]
     fn gitlab() {
-        let url = crate::gitlab(
+        let url = crate::file_url(
+            &crate::Platform::Gitlab,
             "https://gitlab.com/nbouliol/git-files",
             "master",
+            false,
             "readme.md",
             None,
         );
@@ -215,9 +237,11 @@ mod test {
 
     #[test]
     fn gitlab_with_line() {
-        let url = crate::gitlab(
+        let url = crate::file_url(
+            &crate::Platform::Gitlab,
             "https://gitlab.com/nbouliol/git-files",
             "master",
+            false,
             "readme.md",
             Some(5),
         );
@@ -226,6 +250,59 @@ mod test {
         assert!(url.unwrap() == "https://gitlab.com/nbouliol/git-files/-/blob/master/readme.md#L5")
     }
 
+    #[test]
+    fn bitbucket_with_line() {
+        let url = crate::file_url(
+            &crate::Platform::Bitbucket,
+            "https://bitbucket.org/nbouliol/git-files",
+            "master",
+            false,
+            "readme.md",
+            Some(5),
+        );
+
+        assert!(url.is_ok());
+        assert!(
+            url.unwrap() == "https://bitbucket.org/nbouliol/git-files/src/master/readme.md#lines-5"
+        )
+    }
+
+    #[test]
+    fn gitea_branch_with_line() {
+        let url = crate::file_url(
+            &crate::Platform::Gitea,
+            "https://gitea.example.com/nbouliol/git-files",
+            "master",
+            false,
+            "readme.md",
+            Some(5),
+        );
+
+        assert!(url.is_ok());
+        assert!(
+            url.unwrap()
+                == "https://gitea.example.com/nbouliol/git-files/src/branch/master/readme.md#L5"
+        )
+    }
+
+    #[test]
+    fn gitea_commit_with_line() {
+        let url = crate::file_url(
+            &crate::Platform::Gitea,
+            "https://gitea.example.com/nbouliol/git-files",
+            "abc123",
+            true,
+            "readme.md",
+            Some(5),
+        );
+
+        assert!(url.is_ok());
+        assert!(
+            url.unwrap()
+                == "https://gitea.example.com/nbouliol/git-files/src/commit/abc123/readme.md#L5"
+        )
+    }
+
     #[test]
     fn get_url_none() {
         let url = crate::get_url(None);